@@ -1,13 +1,14 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 #![allow(clippy::cast_possible_truncation)]
 
+use ink::prelude::vec;
 use ink::prelude::vec::Vec;
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
 
 
 #[ink::contract]
-mod game_lobby {
+pub mod game_lobby {
     use super::*;
     use ink::storage::traits::StorageLayout;
 
@@ -16,10 +17,19 @@ mod game_lobby {
         owner: AccountId,
         family_id: u32, // games of similar type, i.e. poker games will have same family id to group them
         max_players: u8,
-        players: Vec<AccountId>,
+        team_count: u8, // number of teams players can be split into before a match starts
+        players: Vec<PlayerInfo>,
         state: LobbyState,
     }
 
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(TypeInfo, StorageLayout))]
+    pub struct PlayerInfo {
+        account: AccountId,
+        ready: bool,
+        team: Option<u8>,
+    }
+
     #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Debug)]
     #[cfg_attr(feature = "std", derive(TypeInfo, StorageLayout))]
     pub enum LobbyState {
@@ -36,11 +46,14 @@ mod game_lobby {
         PlayerAlreadyJoined,
         PlayerNotFound,
         NotOwner,
+        NotReady,
+        UnbalancedTeams,
+        InvalidTeam,
     }
 
     impl GameLobby {
         #[ink(constructor)]
-        pub fn new(family_id: u32, max_players: u8) -> Self {
+        pub fn new(family_id: u32, max_players: u8, team_count: u8) -> Self {
             // Caller is the address that is calling this constructor
             let caller = Self::env().caller();
 
@@ -48,11 +61,42 @@ mod game_lobby {
                 owner: caller,
                 family_id,
                 max_players,
+                team_count,
                 players: Vec::new(),
                 state: LobbyState::Registering,
             }
         }
 
+        /// Whether every joined player has marked themselves ready.
+        fn all_ready(&self) -> bool {
+            !self.players.is_empty() && self.players.iter().all(|p| p.ready)
+        }
+
+        /// Whether joined players are evenly split across `team_count` teams.
+        /// A `team_count` of 0 means the lobby doesn't use teams.
+        fn teams_balanced(&self) -> bool {
+            if self.team_count == 0 || self.players.is_empty() {
+                return true;
+            }
+
+            let mut counts = vec![0u8; self.team_count as usize];
+            for player in &self.players {
+                match player.team {
+                    Some(team) if (team as usize) < counts.len() => counts[team as usize] += 1,
+                    _ => return false,
+                }
+            }
+
+            counts.iter().all(|&count| count == counts[0])
+        }
+
+        /// Whether the lobby is full, every player is ready, and teams (if any) are balanced.
+        fn ready_to_start(&self) -> bool {
+            self.players.len() == self.max_players as usize
+                && self.all_ready()
+                && self.teams_balanced()
+        }
+
         #[ink(message)]
         pub fn join(&mut self) -> Result<(), Error> {
             let caller = Self::env().caller();
@@ -60,7 +104,7 @@ mod game_lobby {
             // Carry out series of checks before joining player:
 
             // Check if player already joined
-            if self.players.contains(&caller) {
+            if self.players.iter().any(|p| p.account == caller) {
                 return Err(Error::PlayerAlreadyJoined);
             }
 
@@ -75,10 +119,13 @@ mod game_lobby {
             }
 
             // All is good
-            self.players.push(caller);
+            self.players.push(PlayerInfo {
+                account: caller,
+                ready: false,
+                team: None,
+            });
 
-            // Auto-transition to InPlay if lobby is full
-            if self.players.len() == self.max_players as usize {
+            if self.ready_to_start() {
                 self.state = LobbyState::InPlay;
             }
 
@@ -88,14 +135,14 @@ mod game_lobby {
         #[ink(message)]
         pub fn leave(&mut self) -> Result<(), Error> {
             let caller = Self::env().caller();
-            
+
             // Check if lobby is open for registration
             if self.state != LobbyState::Registering {
                 return Err(Error::LobbyNotOpen);
             }
-            
+
             // Find and remove player
-            if let Some(index) = self.players.iter().position(|p| p == &caller) {
+            if let Some(index) = self.players.iter().position(|p| p.account == caller) {
                 self.players.swap_remove(index);
                 Ok(())
             } else {
@@ -103,15 +150,137 @@ mod game_lobby {
             }
         }
 
+        /// Marks the caller as ready (or not) to start the match.
+        #[ink(message)]
+        pub fn set_ready(&mut self, ready: bool) -> Result<(), Error> {
+            let caller = Self::env().caller();
+
+            // Check if lobby is open for registration
+            if self.state != LobbyState::Registering {
+                return Err(Error::LobbyNotOpen);
+            }
+
+            let player = self
+                .players
+                .iter_mut()
+                .find(|p| p.account == caller)
+                .ok_or(Error::PlayerNotFound)?;
+            player.ready = ready;
+
+            if self.ready_to_start() {
+                self.state = LobbyState::InPlay;
+            }
+
+            Ok(())
+        }
+
+        /// Assigns the caller to `team_id`, one of the teams configured in the constructor.
+        #[ink(message)]
+        pub fn join_team(&mut self, team_id: u8) -> Result<(), Error> {
+            let caller = Self::env().caller();
+
+            // Check if lobby is open for registration
+            if self.state != LobbyState::Registering {
+                return Err(Error::LobbyNotOpen);
+            }
+
+            if team_id >= self.team_count {
+                return Err(Error::InvalidTeam);
+            }
+
+            let player = self
+                .players
+                .iter_mut()
+                .find(|p| p.account == caller)
+                .ok_or(Error::PlayerNotFound)?;
+            player.team = Some(team_id);
+
+            if self.ready_to_start() {
+                self.state = LobbyState::InPlay;
+            }
+
+            Ok(())
+        }
+
         #[ink(message)]
         pub fn get_players(&self) -> Vec<AccountId> {
-            self.players.clone()
+            self.players.iter().map(|p| p.account).collect()
         }
 
         #[ink(message)]
         pub fn get_state(&self) -> LobbyState {
             self.state
         }
+
+        /// Removes `who` from the lobby. Owner-only, and only while still registering.
+        #[ink(message)]
+        pub fn kick_player(&mut self, who: AccountId) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            // Check if lobby is open for registration
+            if self.state != LobbyState::Registering {
+                return Err(Error::LobbyNotOpen);
+            }
+
+            if let Some(index) = self.players.iter().position(|p| p.account == who) {
+                self.players.swap_remove(index);
+                Ok(())
+            } else {
+                Err(Error::PlayerNotFound)
+            }
+        }
+
+        /// Hands ownership of the lobby to `new_owner`. Owner-only.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            self.owner = new_owner;
+            Ok(())
+        }
+
+        /// Manually moves the lobby from `Registering` to `InPlay`, even if it
+        /// isn't full yet. Owner-only; the joined players still need to be
+        /// ready and, if teams are in use, evenly split.
+        #[ink(message)]
+        pub fn force_start(&mut self) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            // Check if lobby is open for registration
+            if self.state != LobbyState::Registering {
+                return Err(Error::LobbyNotOpen);
+            }
+
+            if !self.all_ready() {
+                return Err(Error::NotReady);
+            }
+
+            if !self.teams_balanced() {
+                return Err(Error::UnbalancedTeams);
+            }
+
+            self.state = LobbyState::InPlay;
+            Ok(())
+        }
+
+        /// Clears the player list and returns the lobby to `Registering` so it
+        /// can be reused. Owner-only.
+        #[ink(message)]
+        pub fn reset(&mut self) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            self.players.clear();
+            self.state = LobbyState::Registering;
+            Ok(())
+        }
     }
 
 
@@ -125,20 +294,21 @@ mod game_lobby {
         fn test_new() {
             let accounts = test::default_accounts::<DefaultEnvironment>();
 
-            // create new lobby with family id of and max 4 players.
-            let lobby = GameLobby::new(1, 4);
+            // create new lobby with family id of 1, max 4 players, 2 teams.
+            let lobby = GameLobby::new(1, 4, 2);
 
             // Assert
             assert_eq!(lobby.owner, accounts.alice); // Alice, who is acc#1 is owner
             assert_eq!(lobby.family_id, 1); // check family ID stored
             assert_eq!(lobby.max_players, 4); // check max players
+            assert_eq!(lobby.team_count, 2); // check team count
             assert_eq!(lobby.players.len(), 0); // should be empty
             assert_eq!(lobby.state, LobbyState::Registering); // should default to registering state
         }
 
         #[ink::test]
         fn test_join() {
-            let mut lobby = GameLobby::new(1, 2); // Note: changed max_players to 2
+            let mut lobby = GameLobby::new(1, 2, 0); // Note: changed max_players to 2, no teams
 
             // owner join lobby
             let result = lobby.join();
@@ -153,12 +323,12 @@ mod game_lobby {
 
             assert_eq!(result.is_ok(), true);               // should be ok
             assert_eq!(lobby.players.len(), 2);             // should be 2 players
-            assert_eq!(lobby.state, LobbyState::InPlay);    // should transition to In-Play state
+            assert_eq!(lobby.state, LobbyState::Registering); // full, but nobody's ready yet
         }
 
         #[ink::test]
         fn test_leave(){
-            let mut lobby = GameLobby::new(1, 3);
+            let mut lobby = GameLobby::new(1, 3, 0);
             lobby.join();
 
             test::set_caller::<DefaultEnvironment>(test::default_accounts::<DefaultEnvironment>().bob);
@@ -175,7 +345,7 @@ mod game_lobby {
 
         #[ink::test]
         fn test_get_players() {
-            let mut lobby = GameLobby::new(1, 4);
+            let mut lobby = GameLobby::new(1, 4, 0);
             let accounts = test::default_accounts::<DefaultEnvironment>();
 
             // Alice joins (default caller)
@@ -218,7 +388,7 @@ mod game_lobby {
         #[ink::test]
         fn test_join_fails_when_lobby_is_full() {
             let accounts = test::default_accounts::<DefaultEnvironment>();
-            let mut lobby = GameLobby::new(99, 2); // max_players = 2
+            let mut lobby = GameLobby::new(99, 2, 0); // max_players = 2
 
             // Alice joins
             test::set_caller::<DefaultEnvironment>(accounts.alice);
@@ -243,7 +413,7 @@ mod game_lobby {
         #[ink::test]
         fn test_join_fails_if_already_joined() {
             let accounts = test::default_accounts::<DefaultEnvironment>();
-            let mut lobby = GameLobby::new(1, 3);
+            let mut lobby = GameLobby::new(1, 3, 0);
 
             // Alice joins
             test::set_caller::<DefaultEnvironment>(accounts.alice);
@@ -259,6 +429,153 @@ mod game_lobby {
             assert_eq!(players[0], accounts.alice);
         }
 
+        #[ink::test]
+        fn test_kick_player() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut lobby = GameLobby::new(1, 4, 0); // Alice (default caller) is owner
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(lobby.join(), Ok(()));
+
+            // Non-owner can't kick
+            let result = lobby.kick_player(accounts.bob);
+            assert_eq!(result, Err(Error::NotOwner));
+
+            // Owner kicks Bob
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(lobby.kick_player(accounts.bob), Ok(()));
+            assert!(!lobby.get_players().contains(&accounts.bob));
+
+            // Kicking someone not in the lobby fails
+            assert_eq!(lobby.kick_player(accounts.bob), Err(Error::PlayerNotFound));
+        }
+
+        #[ink::test]
+        fn test_transfer_ownership() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut lobby = GameLobby::new(1, 4, 0); // Alice is owner
+
+            // Non-owner can't transfer
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(lobby.transfer_ownership(accounts.bob), Err(Error::NotOwner));
+
+            // Owner transfers to Bob
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(lobby.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(lobby.owner, accounts.bob);
+        }
+
+        #[ink::test]
+        fn test_force_start() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut lobby = GameLobby::new(1, 4, 0); // not full, no teams
+
+            assert_eq!(lobby.join(), Ok(()));
+
+            // Non-owner can't force-start
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(lobby.force_start(), Err(Error::NotOwner));
+
+            // Owner can't force-start while the only joined player isn't ready
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(lobby.force_start(), Err(Error::NotReady));
+
+            // Once ready, owner can force-start while not full
+            assert_eq!(lobby.set_ready(true), Ok(()));
+            assert_eq!(lobby.force_start(), Ok(()));
+            assert_eq!(lobby.state, LobbyState::InPlay);
+
+            // Can't force-start again once not registering
+            assert_eq!(lobby.force_start(), Err(Error::LobbyNotOpen));
+        }
+
+        #[ink::test]
+        fn test_reset() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut lobby = GameLobby::new(1, 2, 0);
 
+            assert_eq!(lobby.join(), Ok(()));
+            assert_eq!(lobby.set_ready(true), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(lobby.join(), Ok(()));
+            assert_eq!(lobby.set_ready(true), Ok(()));
+            assert_eq!(lobby.state, LobbyState::InPlay);
+
+            // Non-owner can't reset
+            assert_eq!(lobby.reset(), Err(Error::NotOwner));
+
+            // Owner resets the lobby for reuse
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(lobby.reset(), Ok(()));
+            assert_eq!(lobby.state, LobbyState::Registering);
+            assert_eq!(lobby.get_players().len(), 0);
+        }
+
+        #[ink::test]
+        fn test_set_ready_transitions_to_in_play_once_everyone_is_ready() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut lobby = GameLobby::new(1, 2, 0); // no team requirement
+
+            assert_eq!(lobby.join(), Ok(())); // Alice joins
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(lobby.join(), Ok(())); // Bob joins
+
+            // Full, but nobody's ready yet
+            assert_eq!(lobby.state, LobbyState::Registering);
+
+            assert_eq!(lobby.set_ready(true), Ok(())); // Bob marks ready
+            assert_eq!(lobby.state, LobbyState::Registering); // Alice still not ready
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(lobby.set_ready(true), Ok(())); // Alice marks ready
+            assert_eq!(lobby.state, LobbyState::InPlay); // everyone ready now
+        }
+
+        #[ink::test]
+        fn test_set_ready_and_join_team_require_membership() {
+            let mut lobby = GameLobby::new(1, 4, 2);
+
+            assert_eq!(lobby.set_ready(true), Err(Error::PlayerNotFound));
+            assert_eq!(lobby.join_team(0), Err(Error::PlayerNotFound));
+        }
+
+        #[ink::test]
+        fn test_join_team_rejects_invalid_team_id() {
+            let mut lobby = GameLobby::new(1, 4, 2); // valid team ids are 0 and 1
+
+            assert_eq!(lobby.join(), Ok(()));
+            assert_eq!(lobby.join_team(2), Err(Error::InvalidTeam));
+        }
+
+        #[ink::test]
+        fn test_unbalanced_teams_block_start_until_evened_out() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut lobby = GameLobby::new(1, 4, 2); // two teams
+
+            for account in [accounts.alice, accounts.bob, accounts.charlie, accounts.django] {
+                test::set_caller::<DefaultEnvironment>(account);
+                assert_eq!(lobby.join(), Ok(()));
+                assert_eq!(lobby.set_ready(true), Ok(()));
+            }
+
+            // Everyone's ready, but nobody picked a team yet
+            assert_eq!(lobby.state, LobbyState::Registering);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(lobby.join_team(0), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(lobby.join_team(0), Ok(()));
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(lobby.join_team(1), Ok(()));
+
+            // 2 players on team 0, 1 on team 1: unbalanced
+            assert_eq!(lobby.state, LobbyState::Registering);
+
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(lobby.join_team(1), Ok(()));
+
+            // 2-2 split and everyone ready: match can start
+            assert_eq!(lobby.state, LobbyState::InPlay);
+        }
     }
 }
@@ -0,0 +1,180 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+use ink::prelude::vec;
+use ink::prelude::vec::Vec;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+#[ink::contract]
+mod lobby_factory {
+    use super::*;
+    use game_lobby::game_lobby::GameLobbyRef;
+    use ink::storage::Mapping;
+
+    /// Upper bound on how many lobbies this factory keeps indexed at once
+    /// (destroyed lobbies free their slot), mirroring
+    /// the MAX_ROOMS-style ceiling used elsewhere to bound matchmaking state.
+    const MAX_LOBBIES: u32 = 1_000;
+
+    #[ink(storage)]
+    pub struct LobbyFactory {
+        owner: AccountId,
+        game_lobby_code_hash: Hash,
+        lobbies: Mapping<u32, Vec<AccountId>>, // family_id -> lobby addresses
+        lobby_family: Mapping<AccountId, u32>, // lobby address -> family_id, for destroy lookups
+        lobby_count: u32, // number of currently live lobbies, checked against MAX_LOBBIES
+        next_salt: u32, // monotonically increasing, never reused even after a destroy
+    }
+
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    #[cfg_attr(feature = "std", derive(TypeInfo))]
+    pub enum Error {
+        NotOwner,
+        TooManyLobbies,
+        LobbyNotFound,
+    }
+
+    impl LobbyFactory {
+        #[ink(constructor)]
+        pub fn new(game_lobby_code_hash: Hash) -> Self {
+            Self {
+                owner: Self::env().caller(),
+                game_lobby_code_hash,
+                lobbies: Mapping::default(),
+                lobby_family: Mapping::default(),
+                lobby_count: 0,
+                next_salt: 0,
+            }
+        }
+
+        /// Instantiates a new `GameLobby` for `family_id` and indexes its address.
+        #[ink(message)]
+        pub fn create_lobby(&mut self, family_id: u32, max_players: u8) -> Result<AccountId, Error> {
+            if self.lobby_count >= MAX_LOBBIES {
+                return Err(Error::TooManyLobbies);
+            }
+
+            let lobby: GameLobbyRef = GameLobbyRef::new(family_id, max_players, 0)
+                .code_hash(self.game_lobby_code_hash)
+                .endowment(0)
+                .salt_bytes(self.next_salt.to_be_bytes())
+                .instantiate();
+            let address = ink::ToAccountId::to_account_id(&lobby);
+
+            let mut family_lobbies = self.lobbies.get(family_id).unwrap_or_default();
+            family_lobbies.push(address);
+            self.lobbies.insert(family_id, &family_lobbies);
+            self.lobby_family.insert(address, &family_id);
+            self.lobby_count += 1;
+            self.next_salt += 1;
+
+            Ok(address)
+        }
+
+        /// Returns the addresses of the lobbies indexed for `family_id`; lobbies
+        /// removed with `destroy_lobby` are not included.
+        #[ink(message)]
+        pub fn list_lobbies(&self, family_id: u32) -> Vec<AccountId> {
+            self.lobbies.get(family_id).unwrap_or_default()
+        }
+
+        /// Drops `addr` from the index. Owner-only; does not affect the lobby contract itself.
+        #[ink(message)]
+        pub fn destroy_lobby(&mut self, addr: AccountId) -> Result<(), Error> {
+            if Self::env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let family_id = self.lobby_family.take(addr).ok_or(Error::LobbyNotFound)?;
+
+            let mut family_lobbies = self.lobbies.get(family_id).unwrap_or_default();
+            if let Some(index) = family_lobbies.iter().position(|a| a == &addr) {
+                family_lobbies.swap_remove(index);
+                self.lobbies.insert(family_id, &family_lobbies);
+            }
+
+            self.lobby_count = self.lobby_count.saturating_sub(1);
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        #[ink::test]
+        fn test_new() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let code_hash = Hash::from([1u8; 32]);
+
+            let factory = LobbyFactory::new(code_hash);
+
+            assert_eq!(factory.owner, accounts.alice); // Alice is the deployer
+            assert_eq!(factory.game_lobby_code_hash, code_hash);
+            assert_eq!(factory.lobby_count, 0);
+            assert_eq!(factory.next_salt, 0);
+        }
+
+        #[ink::test]
+        fn test_list_lobbies_empty_for_unknown_family() {
+            let factory = LobbyFactory::new(Hash::from([1u8; 32]));
+
+            assert_eq!(factory.list_lobbies(1), Vec::new());
+        }
+
+        #[ink::test]
+        fn test_create_lobby_rejects_once_at_cap() {
+            let mut factory = LobbyFactory::new(Hash::from([1u8; 32]));
+
+            // Simulate the factory already being at the MAX_LOBBIES ceiling,
+            // without needing to actually instantiate that many lobbies.
+            factory.lobby_count = MAX_LOBBIES;
+
+            let result = factory.create_lobby(1, 4);
+            assert_eq!(result, Err(Error::TooManyLobbies));
+        }
+
+        #[ink::test]
+        fn test_destroy_lobby_requires_owner() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut factory = LobbyFactory::new(Hash::from([1u8; 32])); // Alice is owner
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let result = factory.destroy_lobby(accounts.charlie);
+
+            assert_eq!(result, Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn test_destroy_lobby_fails_when_not_found() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut factory = LobbyFactory::new(Hash::from([1u8; 32])); // Alice is owner
+
+            let result = factory.destroy_lobby(accounts.bob);
+
+            assert_eq!(result, Err(Error::LobbyNotFound));
+        }
+
+        #[ink::test]
+        fn test_destroy_lobby_removes_from_index() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut factory = LobbyFactory::new(Hash::from([1u8; 32])); // Alice is owner
+
+            // Index an address as if create_lobby had produced it, since cross-contract
+            // instantiation isn't available in the off-chain test environment.
+            let family_lobbies = vec![accounts.bob];
+            factory.lobbies.insert(1, &family_lobbies);
+            factory.lobby_family.insert(accounts.bob, &1);
+            factory.lobby_count = 1;
+
+            assert_eq!(factory.destroy_lobby(accounts.bob), Ok(()));
+            assert_eq!(factory.list_lobbies(1), Vec::new());
+            assert_eq!(factory.lobby_count, 0);
+
+            // Destroying it again now reports it as gone
+            assert_eq!(factory.destroy_lobby(accounts.bob), Err(Error::LobbyNotFound));
+        }
+    }
+}